@@ -1,18 +1,21 @@
 use std::{
-    collections::HashMap,
+    collections::HashSet,
     ffi::OsString,
-    fs::File,
+    fs::{self, File},
     io::{self, stdin, stdout, BufReader},
+    path::Path,
     str::from_utf8,
 };
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use csv::Writer;
+use clap::{Parser, ValueEnum};
+use csv::{QuoteStyle, ReaderBuilder, Terminator, Writer, WriterBuilder};
+use indexmap::IndexMap;
 use quick_xml::{
-    events::{BytesStart, Event},
+    events::{BytesEnd, BytesStart, BytesText, Event},
     Reader,
 };
+use serde_json::{to_writer, to_writer_pretty, Map, Value};
 
 /// Converts a XML fixtures of typo3/testing-framework into a CSV fixtures.
 #[derive(Parser, Debug)]
@@ -25,16 +28,159 @@ struct CommandLineArguments {
     output_file: Option<OsString>,
     /// File name of the file to read from.
     ///
-    /// [stdin] is read by default.
+    /// [stdin] is read by default. With `--format csv` (the default), this
+    /// switches to a memory-bounded streaming mode that writes a fresh
+    /// table-name-and-header block every time the table name changes,
+    /// instead of merging every occurrence of a table into one block like
+    /// stdin input does. Fixtures where a table's rows aren't contiguous
+    /// convert differently depending on whether `--input-file` or stdin was
+    /// used; a warning is printed to stderr when this happens.
     #[arg(short, long)]
     input_file: Option<OsString>,
+    /// Field delimiter to use in the output CSV.
+    #[arg(long, default_value = ",", value_parser = parse_single_byte)]
+    delimiter: u8,
+    /// Quote character to use in the output CSV.
+    #[arg(long, default_value = "\"", value_parser = parse_single_byte)]
+    quote: u8,
+    /// When fields should be quoted in the output CSV.
+    #[arg(long, value_enum, default_value_t = QuoteStyleArgument::Necessary)]
+    quote_style: QuoteStyleArgument,
+    /// Line terminator to use between records in the output CSV.
+    ///
+    /// Defaults to the `csv` crate's `\n` terminator.
+    #[arg(long, value_parser = parse_single_byte)]
+    terminator: Option<u8>,
+    /// Read a TYPO3 CSV fixture and regenerate the equivalent `<dataset>`
+    /// XML instead of converting XML to CSV.
+    ///
+    /// Errors when combined with `--input-dir`/`--output-dir`, which only
+    /// ever convert XML fixtures to CSV.
+    #[arg(long)]
+    reverse: bool,
+    /// Directory containing `.xml` fixtures to batch-convert.
+    ///
+    /// When set together with [Self::output_dir], every `.xml` file in this
+    /// directory is converted to a correspondingly named `.csv` file instead
+    /// of converting a single `--input-file`/`--output-file` pair. Errors if
+    /// combined with `--reverse` or a non-`csv` `--format`.
+    #[arg(long)]
+    input_dir: Option<OsString>,
+    /// Directory to write batch-converted `.csv` fixtures to.
+    ///
+    /// Created (including parent directories) if it does not exist yet.
+    #[arg(long)]
+    output_dir: Option<OsString>,
+    /// Keep converting the remaining fixtures in `--input-dir` after one of
+    /// them fails, instead of aborting the whole run on the first malformed
+    /// fixture.
+    #[arg(long)]
+    continue_on_error: bool,
+    /// Output format to emit the parsed dataset as.
+    ///
+    /// `json` and `ndjson` error out together with `--input-dir`, which
+    /// always writes the TYPO3 CSV layout. Streaming large `--input-file`s
+    /// also only ever writes the CSV layout, but falls back to the
+    /// in-memory path instead of erroring when a non-`csv` format is
+    /// requested.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+}
+
+/// Output format that the parsed dataset can be emitted as.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// The TYPO3 CSV layout produced by [convert_dataset] today.
+    Csv,
+    /// An object keyed by table name whose value is an array of record
+    /// objects (column name to value).
+    Json,
+    /// One JSON object per record, with an added `_table` field, so the
+    /// stream stays flat.
+    Ndjson,
+}
+
+/// Mirrors [csv::QuoteStyle] so it can be derived as a [clap] value enum.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum QuoteStyleArgument {
+    Always,
+    Necessary,
+    NonNumeric,
+    Never,
+}
+
+impl From<QuoteStyleArgument> for QuoteStyle {
+    fn from(value: QuoteStyleArgument) -> Self {
+        match value {
+            QuoteStyleArgument::Always => QuoteStyle::Always,
+            QuoteStyleArgument::Necessary => QuoteStyle::Necessary,
+            QuoteStyleArgument::NonNumeric => QuoteStyle::NonNumeric,
+            QuoteStyleArgument::Never => QuoteStyle::Never,
+        }
+    }
+}
+
+/// Parses a command line option that must be exactly one byte, e.g. a
+/// delimiter or quote character.
+fn parse_single_byte(value: &str) -> std::result::Result<u8, String> {
+    let mut bytes = value.bytes();
+    match (bytes.next(), bytes.next()) {
+        (Some(byte), None) => Ok(byte),
+        _ => Err(format!(
+            "expected a single ASCII byte character, got {:?}",
+            value
+        )),
+    }
 }
 
 fn main() -> Result<()> {
     let command_line_arguments = CommandLineArguments::parse();
+
+    if let (Some(input_dir), Some(output_dir)) = (
+        &command_line_arguments.input_dir,
+        &command_line_arguments.output_dir,
+    ) {
+        if command_line_arguments.reverse {
+            return Err(anyhow::anyhow!(
+                "--reverse is not supported together with --input-dir/--output-dir. Batch mode only converts XML fixtures to CSV."
+            ));
+        }
+        if command_line_arguments.format != OutputFormat::Csv {
+            return Err(anyhow::anyhow!(
+                "--format {:?} is not supported together with --input-dir/--output-dir. Batch mode always writes the TYPO3 CSV layout.",
+                command_line_arguments.format
+            ));
+        }
+
+        return convert_directory(&command_line_arguments, input_dir, output_dir);
+    }
+
+    if command_line_arguments.reverse {
+        let csv_reader = create_csv_reader(&command_line_arguments)?;
+        let xml_writer = create_xml_writer(&command_line_arguments)?;
+        return convert_csv_to_dataset(csv_reader, xml_writer);
+    }
+
+    // Streaming needs to reopen and re-read the input file for its second
+    // pass, which isn't possible when reading from stdin. It only knows
+    // how to write the CSV sink, so non-CSV formats fall through to the
+    // in-memory path below.
+    if command_line_arguments.format == OutputFormat::Csv {
+        if let Some(input_file_name) = &command_line_arguments.input_file {
+            let writer = create_csv_writer(&command_line_arguments)?;
+            return convert_dataset_streaming(input_file_name, writer);
+        }
+    }
+
     let mut reader = create_xml_reader(&command_line_arguments)?;
-    let writer = create_csv_writer(&command_line_arguments)?;
+    skip_to_dataset_start(&mut reader)?;
+    let output = open_output(&command_line_arguments)?;
+    convert_dataset(reader, output, &command_line_arguments)
+}
 
+/// Advances [reader] past the `<dataset>` starting tag, so that it is
+/// positioned to read the `<dataset>` element's children.
+fn skip_to_dataset_start<R: io::BufRead>(reader: &mut Reader<R>) -> Result<()> {
     let mut buf = Vec::new();
 
     loop {
@@ -46,7 +192,7 @@ fn main() -> Result<()> {
                     error
                 ));
             }
-            Ok(Event::Eof) => break,
+            Ok(Event::Eof) => return Err(anyhow::anyhow!("Input file is empty")),
             Ok(Event::Start(start_event)) => {
                 if start_event.name().as_ref() != b"dataset" {
                     return Err(anyhow::anyhow!(
@@ -54,12 +200,12 @@ fn main() -> Result<()> {
                         reader.buffer_position()
                         ));
                 }
-                return convert_dataset(reader, writer);
+                return Ok(());
             }
             Ok(Event::Decl(_)) | Ok(Event::Text(_)) | Ok(Event::Comment(_)) => continue,
             token => {
                 return Err(create_unexpected_token_error(
-                    &reader,
+                    reader,
                     format!(
                         "Erroring token is {:?}. Expected to find the start of a <dataset> element. ",
                         token
@@ -68,8 +214,99 @@ fn main() -> Result<()> {
             }
         }
     }
+}
+
+/// Converts every `.xml` fixture in [input_dir] to a correspondingly named
+/// `.csv` fixture in [output_dir].
+///
+/// When [CommandLineArguments::continue_on_error] is set, a fixture that
+/// fails to convert is recorded and reported in a summary at the end
+/// instead of aborting the whole run.
+fn convert_directory(
+    command_line_arguments: &CommandLineArguments,
+    input_dir: &OsString,
+    output_dir: &OsString,
+) -> Result<()> {
+    fs::create_dir_all(output_dir).with_context(|| {
+        format!(
+            "Failed to create output directory {}",
+            Path::new(output_dir).display()
+        )
+    })?;
+
+    let mut failed_fixtures = Vec::new();
+
+    for entry in fs::read_dir(input_dir).with_context(|| {
+        format!(
+            "Failed to read input directory {}",
+            Path::new(input_dir).display()
+        )
+    })? {
+        let input_path = entry.context("Failed to read input directory entry")?.path();
+
+        if input_path.extension().and_then(|extension| extension.to_str()) != Some("xml") {
+            continue;
+        }
 
-    Err(anyhow::anyhow!("Input file is empty"))
+        let output_path = Path::new(output_dir).join(input_path.with_extension("csv").file_name().unwrap());
+
+        if let Err(error) = convert_file(command_line_arguments, &input_path, &output_path) {
+            if !command_line_arguments.continue_on_error {
+                return Err(error).with_context(|| {
+                    format!("Failed to convert fixture {}", input_path.display())
+                });
+            }
+
+            eprintln!(
+                "Warning: Failed to convert fixture {}: {:?}",
+                input_path.display(),
+                error
+            );
+            failed_fixtures.push(input_path);
+        }
+    }
+
+    if !failed_fixtures.is_empty() {
+        eprintln!(
+            "Warning: {} of the fixtures in {} could not be converted:",
+            failed_fixtures.len(),
+            Path::new(input_dir).display()
+        );
+        for failed_fixture in &failed_fixtures {
+            eprintln!("  {}", failed_fixture.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts a single XML fixture at [input_path] into a CSV fixture at
+/// [output_path], using the dialect options from [command_line_arguments].
+fn convert_file(
+    command_line_arguments: &CommandLineArguments,
+    input_path: &Path,
+    output_path: &Path,
+) -> Result<()> {
+    let input_fd = File::open(input_path)
+        .with_context(|| format!("Failed to open input file {}.", input_path.display()))?;
+    let mut reader = Reader::from_reader(BufReader::new(input_fd));
+    skip_to_dataset_start(&mut reader)?;
+
+    let dataset = read_dataset(reader)?;
+    let tables = group_entries_into_tables(&dataset)?;
+    let number_of_columns = match compute_number_of_columns(&tables) {
+        Some(number_of_columns) => number_of_columns,
+        None => return Ok(()),
+    };
+
+    let output_fd = File::create(output_path)
+        .with_context(|| format!("Failed to create output file {}.", output_path.display()))?;
+    let mut sink = CsvSink::new(
+        build_csv_writer_builder(command_line_arguments).from_writer(output_fd),
+        number_of_columns,
+    );
+
+    write_tables_to_sink(&mut sink, &tables)
 }
 
 fn create_unexpected_token_error<R: io::BufRead>(
@@ -86,16 +323,37 @@ fn create_unexpected_token_error<R: io::BufRead>(
 /// Convert the `<dataset>` element
 ///
 /// [reader] must be inside the `<dataset>` element.
-fn convert_dataset<R: io::BufRead, W: io::Write>(
+fn convert_dataset<R: io::BufRead>(
     reader: Reader<R>,
-    mut writer: Writer<W>,
+    output: Box<dyn io::Write>,
+    command_line_arguments: &CommandLineArguments,
 ) -> Result<()> {
     let dataset = read_dataset(reader)?;
+    let tables = group_entries_into_tables(&dataset)?;
+    let number_of_columns = match compute_number_of_columns(&tables) {
+        Some(number_of_columns) => number_of_columns,
+        None => return Ok(()),
+    };
 
-    // Table occurrences need to be grouped because the table columns
-    // definition is only read on the first occurrence of the table:
-    // https://github.com/TYPO3/testing-framework/blob/7.0.4/Classes/Core/Functional/Framework/DataHandling/DataSet.php#L100-L102
-    let mut tables: HashMap<&String, TableDataSet> = HashMap::new();
+    let mut sink: Box<dyn DatasetSink> = match command_line_arguments.format {
+        OutputFormat::Csv => Box::new(CsvSink::new(
+            build_csv_writer_builder(command_line_arguments).from_writer(output),
+            number_of_columns,
+        )),
+        OutputFormat::Json => Box::new(JsonSink::new(output)),
+        OutputFormat::Ndjson => Box::new(NdjsonSink::new(output)),
+    };
+
+    write_tables_to_sink(sink.as_mut(), &tables)
+}
+
+/// Groups [dataset]'s entries by table name.
+///
+/// Table occurrences need to be grouped because the table columns
+/// definition is only read on the first occurrence of the table:
+/// https://github.com/TYPO3/testing-framework/blob/7.0.4/Classes/Core/Functional/Framework/DataHandling/DataSet.php#L100-L102
+fn group_entries_into_tables(dataset: &[TableEntry]) -> Result<IndexMap<&String, TableDataSet<'_>>> {
+    let mut tables: IndexMap<&String, TableDataSet> = IndexMap::new();
 
     for entry in dataset.iter() {
         let table = match tables.get_mut(&entry.name) {
@@ -109,54 +367,351 @@ fn convert_dataset<R: io::BufRead, W: io::Write>(
         table.add_entry(entry)?;
     }
 
-    let number_of_columns = match tables.values().map(|table| table.column_names.len()).max() {
+    Ok(tables)
+}
+
+/// The width every CSV row is padded to, i.e. the number of columns of the
+/// widest table in the dataset. Returns [None] (after printing a warning)
+/// when there is nothing to write.
+fn compute_number_of_columns(tables: &IndexMap<&String, TableDataSet>) -> Option<usize> {
+    match tables.values().map(|table| table.column_names.len()).max() {
         None => {
             eprintln!("Warning: <dataset> element is empty. Nothing will be written. ");
-            return Ok(());
+            None
         }
         Some(0) => {
             eprintln!("Warning: No columns used in any element. Nothing will be written. ");
-            return Ok(());
+            None
         }
-        Some(number_of_columns) => number_of_columns,
-    };
-
-    const EMPTY_STR: &'static str = "";
-    let mut write_buffer: Vec<&str> = vec![EMPTY_STR; number_of_columns + 1];
+        Some(number_of_columns) => Some(number_of_columns),
+    }
+}
 
+/// Writes every table of [tables] to [sink] and flushes it.
+fn write_tables_to_sink(
+    sink: &mut dyn DatasetSink,
+    tables: &IndexMap<&String, TableDataSet>,
+) -> Result<()> {
     for (table_name, table_data_set) in tables {
-        write_buffer.fill_with(|| EMPTY_STR);
-        write_buffer[0] = table_name;
+        sink.write_table(
+            table_name,
+            &table_data_set.column_names,
+            &table_data_set.entries,
+        )?;
+    }
+
+    sink.finish()
+}
+
+/// Destination that a converted dataset's tables are written to, one table
+/// at a time, independent of the on-disk format.
+///
+/// [write_table] preserves the `uid`-first column order and the
+/// last-seen-wins behaviour for duplicate cells regardless of the chosen
+/// sink, since both are already resolved by [TableDataSet] before a sink
+/// ever sees the data.
+trait DatasetSink {
+    fn write_table(
+        &mut self,
+        table_name: &str,
+        column_names: &[&str],
+        entries: &[&TableEntry],
+    ) -> Result<()>;
+
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// Writes the TYPO3 CSV layout, padding every row to the leading table-name
+/// column plus [Self::number_of_columns] columns, the widest table in the
+/// whole dataset.
+struct CsvSink<W: io::Write> {
+    writer: Writer<W>,
+    number_of_columns: usize,
+}
+
+impl<W: io::Write> CsvSink<W> {
+    pub fn new(writer: Writer<W>, number_of_columns: usize) -> Self {
+        Self {
+            writer,
+            number_of_columns,
+        }
+    }
+}
+
+impl<W: io::Write> DatasetSink for CsvSink<W> {
+    fn write_table(
+        &mut self,
+        table_name: &str,
+        column_names: &[&str],
+        entries: &[&TableEntry],
+    ) -> Result<()> {
+        const EMPTY_STR: &str = "";
+        let mut write_buffer: Vec<&str> = vec![EMPTY_STR; self.number_of_columns + 1];
 
-        writer
+        write_buffer[0] = table_name;
+        self.writer
             .write_record(&write_buffer)
             .context("Failed to write csv table name row")?;
 
-        let table_group_column_len = table_data_set.column_names.len();
-        write_buffer[1..table_group_column_len + 1]
-            .copy_from_slice(&table_data_set.column_names[..]);
+        write_buffer[1..column_names.len() + 1].copy_from_slice(column_names);
         write_buffer[0] = EMPTY_STR;
-
-        writer
+        self.writer
             .write_record(&write_buffer)
             .context("Failed to write csv column header row")?;
 
-        for entry in table_data_set.entries {
+        for entry in entries {
             write_buffer.fill_with(|| EMPTY_STR);
 
-            table_data_set.column_names.iter().enumerate().for_each(
-                |(column_index, column_name)| {
-                    write_buffer[column_index + 1] = match entry.cells.get(column_name.to_owned()) {
-                        Some(column_payload) => column_payload,
-                        None => "",
-                    }
-                },
-            );
+            column_names.iter().enumerate().for_each(|(column_index, column_name)| {
+                write_buffer[column_index + 1] = match entry.cells.get(column_name.to_owned()) {
+                    Some(column_payload) => column_payload,
+                    None => "",
+                }
+            });
 
-            writer
+            self.writer
                 .write_record(&write_buffer)
                 .context("Failed to write csv data row")?;
         }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush().context("Failed to flush csv")
+    }
+}
+
+/// Writes an object keyed by table name whose value is an array of record
+/// objects (column name to value), buffering the whole dataset in memory
+/// until [Self::finish].
+///
+/// Relies on serde_json's `preserve_order` feature (enabled in Cargo.toml)
+/// so [Map] keeps keys in insertion order; without it, [Map] is backed by a
+/// `BTreeMap` and sorts keys alphabetically, undoing the first-seen table
+/// and column ordering [IndexMap] guarantees everywhere else in this file.
+struct JsonSink {
+    output: Box<dyn io::Write>,
+    tables: Map<String, Value>,
+}
+
+impl JsonSink {
+    pub fn new(output: Box<dyn io::Write>) -> Self {
+        Self {
+            output,
+            tables: Map::new(),
+        }
+    }
+}
+
+impl DatasetSink for JsonSink {
+    fn write_table(
+        &mut self,
+        table_name: &str,
+        column_names: &[&str],
+        entries: &[&TableEntry],
+    ) -> Result<()> {
+        let records = entries
+            .iter()
+            .map(|entry| Value::Object(build_record_object(column_names, entry)))
+            .collect();
+
+        self.tables
+            .insert(table_name.to_owned(), Value::Array(records));
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let tables = std::mem::take(&mut self.tables);
+        to_writer_pretty(&mut self.output, &Value::Object(tables))
+            .context("Failed to write json output")
+    }
+}
+
+/// Writes one JSON object per record, with an added `_table` field, so the
+/// stream stays flat.
+struct NdjsonSink {
+    output: Box<dyn io::Write>,
+}
+
+impl NdjsonSink {
+    pub fn new(output: Box<dyn io::Write>) -> Self {
+        Self { output }
+    }
+}
+
+impl DatasetSink for NdjsonSink {
+    fn write_table(
+        &mut self,
+        table_name: &str,
+        column_names: &[&str],
+        entries: &[&TableEntry],
+    ) -> Result<()> {
+        if column_names.contains(&"_table") {
+            return Err(anyhow::anyhow!(
+                "Table `{}` has a column named `_table`, which collides with the field ndjson output adds to every record to carry the table name. ",
+                table_name
+            ));
+        }
+
+        for entry in entries {
+            let mut record = build_record_object(column_names, entry);
+            record.insert("_table".to_owned(), Value::String(table_name.to_owned()));
+
+            to_writer(&mut self.output, &Value::Object(record))
+                .context("Failed to write ndjson record")?;
+            self.output
+                .write_all(b"\n")
+                .context("Failed to write ndjson newline")?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.output.flush().context("Failed to flush ndjson output")
+    }
+}
+
+/// Builds one JSON record object (column name to value) for [entry], using
+/// empty strings for columns [entry] doesn't have a cell for, mirroring the
+/// CSV sink's padding.
+fn build_record_object(column_names: &[&str], entry: &TableEntry) -> Map<String, Value> {
+    let mut record = Map::new();
+
+    for column_name in column_names {
+        let value = entry
+            .cells
+            .get(column_name.to_owned())
+            .map(String::as_str)
+            .unwrap_or("");
+        record.insert((*column_name).to_owned(), Value::String(value.to_owned()));
+    }
+
+    record
+}
+
+/// A memory-bounded alternative to [convert_dataset] for huge fixtures.
+///
+/// [input_file_name] is read twice: a first pass computes, per table, the
+/// union of column names and the `uid`-first ordering without retaining any
+/// parsed entries, and a second pass re-reads the XML and writes each
+/// record row immediately, so the whole dataset never needs to live in
+/// memory at once.
+///
+/// Table occurrences do not need to be contiguous in the source document:
+/// whenever the table name changes (including changing back to a table seen
+/// earlier), a fresh table-name-and-header block is written. Unlike this
+/// function, [convert_dataset] merges every occurrence of a table into a
+/// single block regardless of where it appears, so the same input can
+/// produce different (but equally valid) output depending on whether
+/// streaming kicks in; a warning is printed to stderr when that happens.
+fn convert_dataset_streaming<W: io::Write>(
+    input_file_name: &OsString,
+    mut writer: Writer<W>,
+) -> Result<()> {
+    let table_layouts = compute_table_layouts(input_file_name)?;
+
+    let number_of_columns = match table_layouts
+        .values()
+        .map(|layout| layout.column_names.len())
+        .max()
+    {
+        None => {
+            eprintln!("Warning: <dataset> element is empty. Nothing will be written. ");
+            return Ok(());
+        }
+        Some(0) => {
+            eprintln!("Warning: No columns used in any element. Nothing will be written. ");
+            return Ok(());
+        }
+        Some(number_of_columns) => number_of_columns,
+    };
+
+    let mut current_table_name: Option<String> = None;
+    let mut tables_started: HashSet<String> = HashSet::new();
+
+    let mut reader = open_xml_reader(input_file_name)?;
+    skip_to_dataset_start(&mut reader)?;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::End(end_event)) => {
+                if end_event.name().as_ref() != b"dataset" {
+                    return Err(create_unexpected_token_error(
+                        &reader,
+                        "Expected to find ending tag </dataset> or start of a new table element. ",
+                    ));
+                }
+
+                break;
+            }
+            Ok(Event::Start(start_event)) => {
+                let table_position = reader.buffer_position();
+                // read_entry prints its own warning for duplicated cells, so
+                // only the first pass (compute_table_layouts) suppresses it
+                // and this pass reports it, to avoid printing it twice per entry.
+                let entry =
+                    read_entry(&mut reader, start_event, true).with_context(|| {
+                        format!("Could not read table at position {}.", table_position)
+                    })?;
+                let layout = table_layouts
+                    .get(&entry.name)
+                    .expect("column layout was computed for every table in the first pass");
+
+                if current_table_name.as_deref() != Some(entry.name.as_str()) {
+                    if tables_started.contains(&entry.name) {
+                        eprintln!(
+                            "Warning: Table `{}` is not contiguous in the input. Streaming mode (--input-file with --format csv) writes each of its occurrences as a separate block instead of merging them into one, unlike non-streaming conversion. ",
+                            entry.name
+                        );
+                    }
+                    tables_started.insert(entry.name.clone());
+
+                    let mut header_buffer: Vec<&str> = vec![""; number_of_columns + 1];
+                    header_buffer[0] = entry.name.as_str();
+                    writer
+                        .write_record(&header_buffer)
+                        .context("Failed to write csv table name row")?;
+
+                    header_buffer.fill_with(|| "");
+                    header_buffer[1..layout.column_names.len() + 1]
+                        .iter_mut()
+                        .zip(layout.column_names.iter())
+                        .for_each(|(cell, column_name)| *cell = column_name.as_str());
+                    writer
+                        .write_record(&header_buffer)
+                        .context("Failed to write csv column header row")?;
+
+                    current_table_name = Some(entry.name.clone());
+                }
+
+                let mut data_buffer: Vec<&str> = vec![""; number_of_columns + 1];
+                layout
+                    .column_names
+                    .iter()
+                    .enumerate()
+                    .for_each(|(column_index, column_name)| {
+                        data_buffer[column_index + 1] = match entry.cells.get(column_name) {
+                            Some(column_payload) => column_payload,
+                            None => "",
+                        }
+                    });
+
+                writer
+                    .write_record(&data_buffer)
+                    .context("Failed to write csv data row")?;
+            }
+            Ok(Event::Text(_)) | Ok(Event::Comment(_)) => continue,
+            _ => {
+                return Err(create_unexpected_token_error(
+                    &reader,
+                    "Expected to find the start of a table element or the end of the dataset. ",
+                ));
+            }
+        }
     }
 
     writer.flush().context("Failed to flush csv")?;
@@ -164,6 +719,214 @@ fn convert_dataset<R: io::BufRead, W: io::Write>(
     Ok(())
 }
 
+/// Per-table column layout computed by the first pass of
+/// [convert_dataset_streaming].
+struct TableLayout {
+    column_names: Vec<String>,
+}
+
+impl TableLayout {
+    pub fn new() -> Self {
+        Self {
+            column_names: Vec::new(),
+        }
+    }
+
+    /// Merges the column names of one entry into this layout, following the
+    /// same first-seen-order and `uid`-first rules as
+    /// [TableDataSet::add_entry].
+    pub fn merge_entry(&mut self, entry: &TableEntry) -> Result<()> {
+        entry.cells.keys().for_each(|cell_column_name| {
+            if !self
+                .column_names
+                .iter()
+                .any(|column_name| column_name == cell_column_name)
+            {
+                self.column_names.push(cell_column_name.clone());
+            }
+        });
+
+        const UID_COLUMN_NAME: &str = "uid";
+        let uid_column_position = self
+            .column_names
+            .iter()
+            .position(|column_name| column_name == UID_COLUMN_NAME)
+            .context("Found a record without uid column")?;
+        if uid_column_position != 0 {
+            self.column_names.swap(0, uid_column_position);
+        }
+
+        Ok(())
+    }
+}
+
+/// First pass of [convert_dataset_streaming]: reads through the whole
+/// `<dataset>` element computing each table's column layout, discarding
+/// every entry once its cell names have been merged in.
+fn compute_table_layouts(input_file_name: &OsString) -> Result<IndexMap<String, TableLayout>> {
+    let mut reader = open_xml_reader(input_file_name)?;
+    skip_to_dataset_start(&mut reader)?;
+
+    let mut table_layouts: IndexMap<String, TableLayout> = IndexMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::End(end_event)) => {
+                if end_event.name().as_ref() != b"dataset" {
+                    return Err(create_unexpected_token_error(
+                        &reader,
+                        "Expected to find ending tag </dataset> or start of a new table element. ",
+                    ));
+                }
+
+                break;
+            }
+            Ok(Event::Start(start_event)) => {
+                let table_position = reader.buffer_position();
+                // Duplicate-cell warnings are reported by the second pass
+                // instead, so the same entry doesn't print them twice.
+                let entry =
+                    read_entry(&mut reader, start_event, false).with_context(|| {
+                        format!("Could not read table at position {}.", table_position)
+                    })?;
+
+                table_layouts
+                    .entry(entry.name.clone())
+                    .or_insert_with(TableLayout::new)
+                    .merge_entry(&entry)?;
+            }
+            Ok(Event::Text(_)) | Ok(Event::Comment(_)) => continue,
+            _ => {
+                return Err(create_unexpected_token_error(
+                    &reader,
+                    "Expected to find the start of a table element or the end of the dataset. ",
+                ));
+            }
+        }
+    }
+
+    Ok(table_layouts)
+}
+
+/// Reads a TYPO3 CSV fixture written by [convert_dataset] and regenerates the
+/// equivalent `<dataset>` XML.
+///
+/// The CSV grouping convention is: a row whose first column is non-empty
+/// starts a new table block, the following row is the column header (first
+/// cell empty), and subsequent rows are records until the next table name
+/// row or EOF.
+fn convert_csv_to_dataset<R: io::Read, W: io::Write>(
+    csv_reader: csv::Reader<R>,
+    mut xml_writer: quick_xml::Writer<W>,
+) -> Result<()> {
+    let tables = read_csv_tables(csv_reader)?;
+
+    xml_writer
+        .write_event(Event::Start(BytesStart::new("dataset")))
+        .context("Failed to write <dataset> start tag")?;
+
+    for (table_name, column_names, rows) in tables {
+        for row in rows {
+            xml_writer
+                .write_event(Event::Start(BytesStart::new(table_name.as_str())))
+                .with_context(|| format!("Failed to write <{}> start tag", table_name))?;
+
+            for (column_name, cell) in column_names.iter().zip(row.iter()) {
+                if cell.is_empty() {
+                    xml_writer
+                        .write_event(Event::Empty(BytesStart::new(column_name.as_str())))
+                        .with_context(|| {
+                            format!("Failed to write empty <{}> element", column_name)
+                        })?;
+                    continue;
+                }
+
+                xml_writer
+                    .write_event(Event::Start(BytesStart::new(column_name.as_str())))
+                    .with_context(|| format!("Failed to write <{}> start tag", column_name))?;
+                xml_writer
+                    .write_event(Event::Text(BytesText::new(cell)))
+                    .with_context(|| format!("Failed to write text of <{}>", column_name))?;
+                xml_writer
+                    .write_event(Event::End(BytesEnd::new(column_name.as_str())))
+                    .with_context(|| format!("Failed to write </{}> end tag", column_name))?;
+            }
+
+            xml_writer
+                .write_event(Event::End(BytesEnd::new(table_name.as_str())))
+                .with_context(|| format!("Failed to write </{}> end tag", table_name))?;
+        }
+    }
+
+    xml_writer
+        .write_event(Event::End(BytesEnd::new("dataset")))
+        .context("Failed to write </dataset> end tag")?;
+
+    Ok(())
+}
+
+/// A `(table name, column names, rows)` triple read from a TYPO3 CSV
+/// fixture, as produced by [read_csv_tables].
+type CsvTable = (String, Vec<String>, Vec<Vec<String>>);
+
+/// Groups the rows of a TYPO3 CSV fixture into [CsvTable]s, following the
+/// grouping convention described on [convert_csv_to_dataset].
+fn read_csv_tables<R: io::Read>(mut csv_reader: csv::Reader<R>) -> Result<Vec<CsvTable>> {
+    let mut tables = Vec::new();
+    let mut records = csv_reader.records().peekable();
+
+    while let Some(table_name_record) = records.next() {
+        let table_name_record = table_name_record.context("Failed to read csv table name row")?;
+        let table_name = table_name_record.get(0).unwrap_or("");
+        if table_name.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Expected a table name in the first column, found an empty cell"
+            ));
+        }
+        let table_name = table_name.to_owned();
+
+        let header_record = records
+            .next()
+            .context("Expected a column header row after the table name row")?
+            .context("Failed to read csv column header row")?;
+        // Every row in the file is padded to the widest table's column
+        // count (see CsvSink::write_table), so narrower tables' header and
+        // data rows have trailing empty cells that aren't real columns.
+        // Column names are never empty, so trailing empty header cells are
+        // unambiguously padding and can be trimmed along with the data
+        // cells at the same positions.
+        let mut column_names: Vec<String> = header_record.iter().skip(1).map(str::to_owned).collect();
+        while column_names.last().is_some_and(String::is_empty) {
+            column_names.pop();
+        }
+
+        let mut rows = Vec::new();
+        while let Some(Ok(peeked)) = records.peek() {
+            if !peeked.get(0).unwrap_or("").is_empty() {
+                break;
+            }
+
+            let record = records
+                .next()
+                .unwrap()
+                .context("Failed to read csv data row")?;
+            rows.push(
+                record
+                    .iter()
+                    .skip(1)
+                    .take(column_names.len())
+                    .map(str::to_owned)
+                    .collect(),
+            );
+        }
+
+        tables.push((table_name, column_names, rows));
+    }
+
+    Ok(tables)
+}
+
 struct TableDataSet<'a> {
     column_names: Vec<&'a str>,
     entries: Vec<&'a TableEntry>,
@@ -227,9 +990,10 @@ fn read_dataset<R: io::BufRead>(mut reader: Reader<R>) -> Result<Vec<TableEntry>
             }
             Ok(Event::Start(start_event)) => {
                 let table_position = reader.buffer_position();
-                let table_data = read_entry(&mut reader, start_event).with_context(|| {
-                    format!("Could not read table at position {}.", table_position)
-                })?;
+                let table_data =
+                    read_entry(&mut reader, start_event, true).with_context(|| {
+                        format!("Could not read table at position {}.", table_position)
+                    })?;
                 data_list.push(table_data)
             }
             Ok(Event::Text(_)) | Ok(Event::Comment(_)) => continue,
@@ -245,9 +1009,14 @@ fn read_dataset<R: io::BufRead>(mut reader: Reader<R>) -> Result<Vec<TableEntry>
     Ok(data_list)
 }
 
+/// Reads one table entry. [report_duplicate_cells] should be `false` when
+/// the caller will also read the same entry again later (e.g. a first pass
+/// that only computes a column layout), so the "Duplicated cell" warning is
+/// only ever printed once per entry.
 fn read_entry<R: io::BufRead>(
     reader: &mut Reader<R>,
     start_event: BytesStart,
+    report_duplicate_cells: bool,
 ) -> Result<TableEntry> {
     let mut table_entry =
         TableEntry::try_from(&start_event).context("Failed to read table start")?;
@@ -280,7 +1049,7 @@ fn read_entry<R: io::BufRead>(
                     })?
                     .to_owned();
 
-                if table_entry.cells.contains_key(&cell_name) {
+                if report_duplicate_cells && table_entry.cells.contains_key(&cell_name) {
                     eprintln!(
                         "Warning: Duplicated cell {} in table {} at position {}",
                         cell_name, table_entry.name, cell_position
@@ -355,7 +1124,7 @@ fn read_entry<R: io::BufRead>(
                     }
                 }
 
-                if table_entry.cells.contains_key(&cell_name) {
+                if report_duplicate_cells && table_entry.cells.contains_key(&cell_name) {
                     eprintln!(
                         "Warning: Duplicated cell {} in table {} at position {}",
                         cell_name, table_entry.name, cell_position
@@ -379,7 +1148,7 @@ fn read_entry<R: io::BufRead>(
 
 struct TableEntry {
     pub name: String,
-    pub cells: HashMap<String, String>,
+    pub cells: IndexMap<String, String>,
 }
 impl TryFrom<&BytesStart<'_>> for TableEntry {
     type Error = anyhow::Error;
@@ -390,7 +1159,7 @@ impl TryFrom<&BytesStart<'_>> for TableEntry {
 
         Ok(Self {
             name: str.to_owned(),
-            cells: HashMap::new(),
+            cells: IndexMap::new(),
         })
     }
 }
@@ -413,12 +1182,25 @@ fn create_xml_reader(
     Ok(Reader::from_reader(bufferred))
 }
 
-fn create_csv_writer(
-    command_line_arguments: &CommandLineArguments,
-) -> Result<Writer<Box<dyn io::Write>>> {
-    let output_fd: Box<dyn io::Write> = match &command_line_arguments.output_file {
-        None => Box::new(stdout()),
-        Some(output_file_name) => Box::new(
+/// Opens [input_file_name] for the streaming path, which needs to reopen
+/// and re-read the file for each of its two passes.
+fn open_xml_reader(input_file_name: &OsString) -> Result<Reader<BufReader<File>>> {
+    let input_fd = File::open(input_file_name).with_context(|| {
+        format!(
+            "Failed to open input file {}.",
+            input_file_name.to_string_lossy()
+        )
+    })?;
+
+    Ok(Reader::from_reader(BufReader::new(input_fd)))
+}
+
+/// Opens the destination named by [CommandLineArguments::output_file], or
+/// [stdout] if none was given.
+fn open_output(command_line_arguments: &CommandLineArguments) -> Result<Box<dyn io::Write>> {
+    match &command_line_arguments.output_file {
+        None => Ok(Box::new(stdout())),
+        Some(output_file_name) => Ok(Box::new(
             File::options()
                 .append(true)
                 .create(true)
@@ -429,8 +1211,387 @@ fn create_csv_writer(
                         output_file_name.to_string_lossy()
                     )
                 })?,
-        ),
+        )),
+    }
+}
+
+/// Builds a [WriterBuilder] configured from the CSV dialect options on
+/// [command_line_arguments].
+fn build_csv_writer_builder(command_line_arguments: &CommandLineArguments) -> WriterBuilder {
+    let mut builder = WriterBuilder::new();
+    builder
+        .delimiter(command_line_arguments.delimiter)
+        .quote(command_line_arguments.quote)
+        .quote_style(command_line_arguments.quote_style.into());
+
+    if let Some(terminator) = command_line_arguments.terminator {
+        builder.terminator(Terminator::Any(terminator));
+    }
+
+    builder
+}
+
+fn create_csv_writer(
+    command_line_arguments: &CommandLineArguments,
+) -> Result<Writer<Box<dyn io::Write>>> {
+    let output_fd = open_output(command_line_arguments)?;
+
+    Ok(build_csv_writer_builder(command_line_arguments).from_writer(output_fd))
+}
+
+fn create_csv_reader(
+    command_line_arguments: &CommandLineArguments,
+) -> Result<csv::Reader<Box<dyn io::Read>>> {
+    let input_fd: Box<dyn io::Read> = match &command_line_arguments.input_file {
+        None => Box::new(stdin()),
+        Some(input_file_name) => Box::new(File::open(input_file_name).with_context(|| {
+            format!(
+                "Failed to open input file {}.",
+                input_file_name.to_string_lossy()
+            )
+        })?),
     };
 
-    Ok(Writer::from_writer(output_fd))
+    let mut builder = ReaderBuilder::new();
+    builder
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(command_line_arguments.delimiter)
+        .quote(command_line_arguments.quote);
+
+    if let Some(terminator) = command_line_arguments.terminator {
+        builder.terminator(Terminator::Any(terminator));
+    }
+
+    Ok(builder.from_reader(input_fd))
+}
+
+fn create_xml_writer(
+    command_line_arguments: &CommandLineArguments,
+) -> Result<quick_xml::Writer<Box<dyn io::Write>>> {
+    let output_fd = open_output(command_line_arguments)?;
+
+    Ok(quick_xml::Writer::new_with_indent(output_fd, b' ', 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, path::PathBuf, rc::Rc};
+
+    const MULTI_TABLE_XML: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="yes" ?>
+<dataset>
+    <pages>
+        <uid>1</uid>
+        <pid>0</pid>
+        <title>Home</title>
+    </pages>
+    <sys_category>
+        <uid>5</uid>
+        <title>Cat</title>
+    </sys_category>
+</dataset>"#;
+
+    fn sample_command_line_arguments() -> CommandLineArguments {
+        CommandLineArguments {
+            output_file: None,
+            input_file: None,
+            delimiter: b',',
+            quote: b'"',
+            quote_style: QuoteStyleArgument::Necessary,
+            terminator: None,
+            reverse: false,
+            input_dir: None,
+            output_dir: None,
+            continue_on_error: false,
+            format: OutputFormat::Csv,
+        }
+    }
+
+    /// An in-memory [io::Write] that can be cloned to keep reading its
+    /// contents after handing ownership of a clone to a [Box<dyn io::Write>]
+    /// or a [Writer].
+    #[derive(Clone)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn new() -> Self {
+            Self(Rc::new(RefCell::new(Vec::new())))
+        }
+
+        fn contents(&self) -> Vec<u8> {
+            self.0.borrow().clone()
+        }
+    }
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.borrow_mut().flush()
+        }
+    }
+
+    fn convert_xml_str_to_csv(xml: &str) -> Result<Vec<u8>> {
+        convert_xml_str_to_csv_with(xml, &sample_command_line_arguments())
+    }
+
+    fn convert_xml_str_to_csv_with(
+        xml: &str,
+        command_line_arguments: &CommandLineArguments,
+    ) -> Result<Vec<u8>> {
+        let mut reader = Reader::from_reader(BufReader::new(xml.as_bytes()));
+        skip_to_dataset_start(&mut reader)?;
+
+        let buffer = SharedBuffer::new();
+        convert_dataset(reader, Box::new(buffer.clone()), command_line_arguments)?;
+
+        Ok(buffer.contents())
+    }
+
+    #[test]
+    fn csv_dialect_flags_are_applied_to_output() {
+        let command_line_arguments = CommandLineArguments {
+            delimiter: b';',
+            quote: b'\'',
+            quote_style: QuoteStyleArgument::Always,
+            terminator: Some(b'\n'),
+            ..sample_command_line_arguments()
+        };
+
+        let csv = convert_xml_str_to_csv_with(MULTI_TABLE_XML, &command_line_arguments).unwrap();
+        let csv = from_utf8(&csv).unwrap();
+
+        // `;` delimiter, `'` quoting on every field, and a bare `\n`
+        // terminator with no CRLF anywhere.
+        assert!(csv.contains("'pages';'';'';''\n"));
+        assert!(!csv.contains("\r\n"));
+    }
+
+    #[test]
+    fn csv_to_xml_round_trip_preserves_tables_with_different_column_counts() {
+        let forward_csv = convert_xml_str_to_csv(MULTI_TABLE_XML).unwrap();
+
+        let csv_reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(forward_csv.as_slice());
+        let xml_buffer = SharedBuffer::new();
+        convert_csv_to_dataset(
+            csv_reader,
+            quick_xml::Writer::new_with_indent(xml_buffer.clone(), b' ', 4),
+        )
+        .unwrap();
+        let regenerated_xml = String::from_utf8(xml_buffer.contents()).unwrap();
+
+        // Re-converting the regenerated XML must produce the exact same CSV,
+        // even though `pages` and `sys_category` have different column
+        // counts and every row in the file is padded to the widest table's
+        // width.
+        let round_tripped_csv = convert_xml_str_to_csv(&regenerated_xml).unwrap();
+        assert_eq!(forward_csv, round_tripped_csv);
+    }
+
+    fn convert_xml_str_to_csv_streaming(xml: &str) -> Vec<u8> {
+        let input_file_name = std::env::temp_dir().join(format!(
+            "{}-{:p}-streaming-test.xml",
+            std::process::id(),
+            xml
+        ));
+        fs::write(&input_file_name, xml).unwrap();
+
+        let streaming_buffer = SharedBuffer::new();
+        let result = convert_dataset_streaming(
+            &OsString::from(&input_file_name),
+            build_csv_writer_builder(&sample_command_line_arguments())
+                .from_writer(streaming_buffer.clone()),
+        );
+        fs::remove_file(&input_file_name).unwrap();
+        result.unwrap();
+
+        streaming_buffer.contents()
+    }
+
+    #[test]
+    fn streaming_conversion_matches_in_memory_conversion() {
+        // Streaming and in-memory conversion only agree when every table's
+        // rows are contiguous: in-memory grouping merges all of a table's
+        // rows into a single block regardless of where they appear, while
+        // streaming writes a fresh block per contiguous run (see
+        // `streaming_conversion_writes_a_fresh_block_per_contiguous_run`
+        // below), so this fixture deliberately keeps each table contiguous.
+        let in_memory_csv = convert_xml_str_to_csv(MULTI_TABLE_XML).unwrap();
+        let streaming_csv = convert_xml_str_to_csv_streaming(MULTI_TABLE_XML);
+
+        assert_eq!(in_memory_csv, streaming_csv);
+    }
+
+    const INTERLEAVED_TABLE_XML: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="yes" ?>
+<dataset>
+    <pages>
+        <uid>1</uid>
+        <pid>0</pid>
+        <title>Home</title>
+    </pages>
+    <sys_category>
+        <uid>5</uid>
+        <title>Cat</title>
+    </sys_category>
+    <pages>
+        <uid>2</uid>
+        <pid>1</pid>
+        <title>About</title>
+    </pages>
+</dataset>"#;
+
+    #[test]
+    fn streaming_conversion_writes_a_fresh_block_per_contiguous_run() {
+        let streaming_csv = convert_xml_str_to_csv_streaming(INTERLEAVED_TABLE_XML);
+        let streaming_csv = from_utf8(&streaming_csv).unwrap();
+
+        // `pages` appears in two non-contiguous runs, so it must be written
+        // as two separate name+header blocks instead of being merged into
+        // one, which would misattribute the second run's rows to whatever
+        // table was written most recently.
+        assert_eq!(streaming_csv.matches("pages,,,\n").count(), 2);
+        assert_eq!(streaming_csv.matches("sys_category,,,\n").count(), 1);
+    }
+
+    /// Creates a fresh input directory under a unique temporary directory,
+    /// populated from `(file name, contents)` pairs, and picks an output
+    /// directory path beside it without creating it. Returns `(base dir,
+    /// input dir, output dir)`; the caller is responsible for removing the
+    /// base dir once done.
+    fn setup_batch_directories(fixtures: &[(&str, &str)]) -> (PathBuf, PathBuf, PathBuf) {
+        let base_dir = std::env::temp_dir().join(format!(
+            "{}-{:p}-batch-test",
+            std::process::id(),
+            fixtures
+        ));
+        let input_dir = base_dir.join("in");
+        let output_dir = base_dir.join("out");
+        fs::create_dir_all(&input_dir).unwrap();
+
+        for (file_name, contents) in fixtures {
+            fs::write(input_dir.join(file_name), contents).unwrap();
+        }
+
+        (base_dir, input_dir, output_dir)
+    }
+
+    #[test]
+    fn batch_conversion_continues_after_an_error_when_requested() {
+        let (base_dir, input_dir, output_dir) =
+            setup_batch_directories(&[("good.xml", MULTI_TABLE_XML), ("bad.xml", "not xml")]);
+
+        let command_line_arguments = CommandLineArguments {
+            continue_on_error: true,
+            ..sample_command_line_arguments()
+        };
+        let result = convert_directory(
+            &command_line_arguments,
+            &input_dir.clone().into_os_string(),
+            &output_dir.clone().into_os_string(),
+        );
+
+        assert!(result.is_ok());
+        assert!(output_dir.join("good.csv").exists());
+        assert!(!output_dir.join("bad.csv").exists());
+
+        fs::remove_dir_all(&base_dir).unwrap();
+    }
+
+    #[test]
+    fn batch_conversion_aborts_on_the_first_error_by_default() {
+        let (base_dir, input_dir, output_dir) =
+            setup_batch_directories(&[("good.xml", MULTI_TABLE_XML), ("bad.xml", "not xml")]);
+
+        let result = convert_directory(
+            &sample_command_line_arguments(),
+            &input_dir.clone().into_os_string(),
+            &output_dir.clone().into_os_string(),
+        );
+
+        assert!(result.is_err());
+
+        fs::remove_dir_all(&base_dir).unwrap();
+    }
+
+    const UNSORTED_ORDER_XML: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="yes" ?>
+<dataset>
+    <zzz_table>
+        <zcol>a</zcol>
+        <uid>1</uid>
+        <acol>b</acol>
+    </zzz_table>
+    <aaa_table>
+        <bcol>c</bcol>
+        <uid>2</uid>
+    </aaa_table>
+</dataset>"#;
+
+    #[test]
+    fn json_sink_preserves_first_seen_table_and_column_order() {
+        let command_line_arguments = CommandLineArguments {
+            format: OutputFormat::Json,
+            ..sample_command_line_arguments()
+        };
+        let json =
+            convert_xml_str_to_csv_with(UNSORTED_ORDER_XML, &command_line_arguments).unwrap();
+        let json = from_utf8(&json).unwrap();
+
+        let zzz_table_index = json.find("zzz_table").unwrap();
+        let aaa_table_index = json.find("aaa_table").unwrap();
+        assert!(
+            zzz_table_index < aaa_table_index,
+            "table order should be first-seen, not alphabetical: {json}"
+        );
+
+        let zcol_index = json.find("\"zcol\"").unwrap();
+        let acol_index = json.find("\"acol\"").unwrap();
+        assert!(
+            zcol_index < acol_index,
+            "column order should be first-seen, not alphabetical: {json}"
+        );
+    }
+
+    #[test]
+    fn ndjson_sink_writes_one_line_per_record_with_table_name() {
+        let command_line_arguments = CommandLineArguments {
+            format: OutputFormat::Ndjson,
+            ..sample_command_line_arguments()
+        };
+        let ndjson =
+            convert_xml_str_to_csv_with(MULTI_TABLE_XML, &command_line_arguments).unwrap();
+        let ndjson = from_utf8(&ndjson).unwrap();
+
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(r#","_table":"pages"}"#));
+        assert!(lines[1].ends_with(r#","_table":"sys_category"}"#));
+    }
+
+    const TABLE_WITH_UNDERSCORE_TABLE_COLUMN_XML: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="yes" ?>
+<dataset>
+    <pages>
+        <uid>1</uid>
+        <_table>oops</_table>
+    </pages>
+</dataset>"#;
+
+    #[test]
+    fn ndjson_sink_errors_on_a_column_named_table() {
+        let command_line_arguments = CommandLineArguments {
+            format: OutputFormat::Ndjson,
+            ..sample_command_line_arguments()
+        };
+        let result = convert_xml_str_to_csv_with(
+            TABLE_WITH_UNDERSCORE_TABLE_COLUMN_XML,
+            &command_line_arguments,
+        );
+
+        assert!(result.is_err());
+    }
 }